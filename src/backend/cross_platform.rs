@@ -0,0 +1,230 @@
+//! The cross-platform backend, backed by `bluest` (CoreBluetooth on macOS, WinRT on
+//! Windows). Used on every target that isn't Linux.
+use super::{Adapter as AdapterTrait, AdapterInfo, Backend, Device as DeviceTrait, PropertyChange};
+use crate::config::{Config, Scan};
+use bluest::DeviceId;
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use futures::stream::StreamExt;
+use std::pin::Pin;
+use tracing::{instrument, warn};
+
+pub struct CrossPlatform;
+
+/// Unlike `bluer`, `bluest` hangs connect/disconnect/pairing off `Adapter` rather
+/// than `Device`, so a device handle needs to carry its adapter along with it.
+#[derive(Clone)]
+pub struct CrossPlatformDevice {
+    adapter: bluest::Adapter,
+    device: bluest::Device,
+}
+
+pub enum DeviceEvent {
+    ConnectionChanged(bool),
+}
+
+#[async_trait::async_trait]
+impl Backend for CrossPlatform {
+    type Id = DeviceId;
+    type Adapter = bluest::Adapter;
+    type Device = CrossPlatformDevice;
+    type DeviceEvent = DeviceEvent;
+    type DeviceEventStream = Pin<Box<dyn futures::Stream<Item = DeviceEvent> + Send>>;
+    type DiscoveryStream = Pin<Box<dyn futures::Stream<Item = DeviceId> + Send>>;
+    type BatteryStream = Pin<Box<dyn futures::Stream<Item = u8> + Send>>;
+
+    #[instrument(skip(config))]
+    async fn get_adapter(config: &Config) -> Result<Self::Adapter> {
+        if config.adapter.is_some() {
+            // `bluest` doesn't expose a way to pick among multiple adapters by
+            // name, so the setting is ignored here rather than on every platform.
+            warn!("Pinning a specific adapter isn't supported on this platform; ignoring");
+        }
+        let adapter = bluest::Adapter::default()
+            .await
+            .ok_or_else(|| eyre!("No Bluetooth adapter found"))?;
+        adapter
+            .wait_available()
+            .await
+            .wrap_err("Adapter is not available")?;
+        Ok(adapter)
+    }
+
+    fn normalize_event(event: DeviceEvent) -> Option<PropertyChange> {
+        match event {
+            DeviceEvent::ConnectionChanged(connected) => Some(PropertyChange::Connected(connected)),
+        }
+    }
+
+    fn parse_id(_id: &str) -> Option<DeviceId> {
+        // `bluest`'s `DeviceId` doesn't expose a stable string round-trip, so
+        // persisted auto-reconnect entries can't be resolved back to a device on
+        // this platform.
+        None
+    }
+
+    async fn list_adapters() -> Result<Vec<AdapterInfo>> {
+        // `bluest` only ever exposes a single platform-default adapter, with no
+        // name or address to distinguish it by.
+        Ok(vec![AdapterInfo {
+            name: "default".into(),
+            address: String::new(),
+            powered: true,
+        }])
+    }
+}
+
+#[async_trait::async_trait]
+impl AdapterTrait<CrossPlatform> for bluest::Adapter {
+    async fn discover_devices(
+        &self,
+        scan: &Scan,
+    ) -> Result<<CrossPlatform as Backend>::DiscoveryStream> {
+        let events = self
+            .scan(&scan.service_uuids)
+            .await
+            .wrap_err("Failed to start scan")?;
+        Ok(Box::pin(
+            events.map(|advertisement| advertisement.device.id()),
+        ))
+    }
+
+    async fn device(&self, id: DeviceId) -> Result<<CrossPlatform as Backend>::Device> {
+        let device = self
+            .open_device(&id)
+            .await
+            .wrap_err("Failed to get device from id")?;
+        Ok(CrossPlatformDevice {
+            adapter: self.clone(),
+            device,
+        })
+    }
+
+    async fn remove_device(&self, _id: DeviceId) -> Result<()> {
+        // `bluest` has no equivalent to BlueZ's "remove" — forgetting a paired
+        // device is left to the OS's own Bluetooth settings on macOS/Windows.
+        Err(eyre!(
+            "Removing paired devices isn't supported on this platform"
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl DeviceTrait<CrossPlatform> for CrossPlatformDevice {
+    async fn is_paired(&self) -> Result<bool> {
+        self.device
+            .is_paired()
+            .await
+            .wrap_err("Failed to get device paired state")
+    }
+
+    async fn is_connected(&self) -> Result<bool> {
+        Ok(self.device.is_connected().await)
+    }
+
+    async fn is_trusted(&self) -> Result<bool> {
+        // `bluest` has no notion of a "trusted" device; the OS owns that policy.
+        Ok(false)
+    }
+
+    async fn alias(&self) -> Result<String> {
+        self.device.name().wrap_err("Failed to get device alias")
+    }
+
+    async fn rssi(&self) -> Result<Option<i16>> {
+        // `bluest` only reports RSSI on the `AdvertisingDevice` yielded by a scan,
+        // not on a bare `Device` handle, so there's nothing to poll here.
+        Ok(None)
+    }
+
+    async fn events(&self) -> Result<<CrossPlatform as Backend>::DeviceEventStream> {
+        let device = self.device.clone();
+        Ok(Box::pin(
+            self.adapter.device_connection_events(&device).map(|event| {
+                DeviceEvent::ConnectionChanged(matches!(event, bluest::ConnectionEvent::Connected))
+            }),
+        ))
+    }
+
+    async fn pair(&self) -> Result<()> {
+        // `bluest` doesn't separate pairing from connecting: the OS prompts for
+        // pairing (if needed) as part of the connection handshake.
+        self.connect().await
+    }
+
+    async fn connect(&self) -> Result<()> {
+        self.adapter
+            .connect_device(&self.device)
+            .await
+            .wrap_err("Failed to connect device")
+    }
+
+    async fn disconnect(&self) -> Result<()> {
+        self.adapter
+            .disconnect_device(&self.device)
+            .await
+            .wrap_err("Failed to disconnect device")
+    }
+
+    async fn set_trusted(&self, _trusted: bool) -> Result<()> {
+        Err(eyre!("Trusting devices isn't supported on this platform"))
+    }
+
+    async fn set_alias(&self, _alias: String) -> Result<()> {
+        // `bluest` only exposes the device's advertised name, which isn't
+        // something a client can rewrite.
+        Err(eyre!("Renaming devices isn't supported on this platform"))
+    }
+
+    async fn battery(&self) -> Result<Option<u8>> {
+        let Some(characteristic) = battery_characteristic(&self.device).await? else {
+            return Ok(None);
+        };
+        let value = characteristic
+            .read()
+            .await
+            .wrap_err("Failed to read battery level")?;
+        Ok(value.first().copied())
+    }
+
+    async fn battery_notifications(
+        &self,
+    ) -> Result<Option<<CrossPlatform as Backend>::BatteryStream>> {
+        let Some(characteristic) = battery_characteristic(&self.device).await? else {
+            return Ok(None);
+        };
+        let notifications = characteristic
+            .notify()
+            .await
+            .wrap_err("Failed to subscribe to battery notifications")?;
+        Ok(Some(Box::pin(notifications.filter_map(
+            |value| async move { value.ok()?.first().copied() },
+        ))))
+    }
+}
+
+/// Run GATT service and characteristic discovery on the device and look for the
+/// standard Battery Service's level characteristic.
+async fn battery_characteristic(device: &bluest::Device) -> Result<Option<bluest::Characteristic>> {
+    for service in device
+        .discover_services()
+        .await
+        .wrap_err("Failed to get services")?
+    {
+        if service.uuid() != super::battery_service::SERVICE {
+            continue;
+        }
+        for characteristic in service
+            .discover_characteristics()
+            .await
+            .wrap_err("Failed to get characteristics")?
+        {
+            if characteristic.uuid() == super::battery_service::LEVEL_CHARACTERISTIC {
+                return Ok(Some(characteristic));
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,240 @@
+//! The native Linux backend, backed directly by `bluer` (BlueZ over D-Bus).
+use super::{Adapter as AdapterTrait, AdapterInfo, Backend, Device as DeviceTrait, PropertyChange};
+use crate::config::{Config, Scan};
+use bluer::{Address, DeviceEvent, DeviceProperty};
+use color_eyre::{eyre::Context, Result};
+use futures::stream::StreamExt;
+use std::pin::Pin;
+use tracing::instrument;
+
+pub struct Linux;
+
+#[async_trait::async_trait]
+impl Backend for Linux {
+    type Id = Address;
+    type Adapter = bluer::Adapter;
+    type Device = bluer::Device;
+    type DeviceEvent = DeviceEvent;
+    type DeviceEventStream = Pin<Box<dyn futures::Stream<Item = DeviceEvent> + Send>>;
+    type DiscoveryStream = Pin<Box<dyn futures::Stream<Item = Address> + Send>>;
+    type BatteryStream = Pin<Box<dyn futures::Stream<Item = u8> + Send>>;
+
+    #[instrument(skip(config))]
+    async fn get_adapter(config: &Config) -> Result<Self::Adapter> {
+        let session = bluer::Session::new()
+            .await
+            .wrap_err("Failed to create session")?;
+        let adapter = match &config.adapter {
+            Some(name) => session
+                .adapter(name)
+                .wrap_err("Failed to get configured adapter")?,
+            None => session
+                .default_adapter()
+                .await
+                .wrap_err("Failed to get default adapter")?,
+        };
+        adapter
+            .set_powered(true)
+            .await
+            .wrap_err("Failed to turn on adapter")?;
+        Ok(adapter)
+    }
+
+    fn normalize_event(event: DeviceEvent) -> Option<PropertyChange> {
+        let DeviceEvent::PropertyChanged(property) = event;
+        match property {
+            DeviceProperty::Alias(alias) => Some(PropertyChange::Alias(alias)),
+            DeviceProperty::Connected(connected) => Some(PropertyChange::Connected(connected)),
+            DeviceProperty::Paired(paired) => Some(PropertyChange::Paired(paired)),
+            DeviceProperty::Trusted(trusted) => Some(PropertyChange::Trusted(trusted)),
+            DeviceProperty::Rssi(rssi) => Some(PropertyChange::Rssi(rssi)),
+            _ => None,
+        }
+    }
+
+    fn parse_id(id: &str) -> Option<Address> {
+        id.parse().ok()
+    }
+
+    async fn list_adapters() -> Result<Vec<AdapterInfo>> {
+        let session = bluer::Session::new()
+            .await
+            .wrap_err("Failed to create session")?;
+        let mut adapters = Vec::new();
+        for name in session
+            .adapter_names()
+            .await
+            .wrap_err("Failed to list adapters")?
+        {
+            let adapter = session.adapter(&name).wrap_err("Failed to get adapter")?;
+            let address = adapter
+                .address()
+                .await
+                .wrap_err("Failed to get adapter address")?;
+            let powered = adapter
+                .is_powered()
+                .await
+                .wrap_err("Failed to get adapter powered state")?;
+            adapters.push(AdapterInfo {
+                name,
+                address: address.to_string(),
+                powered,
+            });
+        }
+        Ok(adapters)
+    }
+}
+
+#[async_trait::async_trait]
+impl AdapterTrait<Linux> for bluer::Adapter {
+    async fn discover_devices(&self, scan: &Scan) -> Result<<Linux as Backend>::DiscoveryStream> {
+        if !scan.service_uuids.is_empty() {
+            let filter = bluer::DiscoveryFilter {
+                uuids: scan.service_uuids.iter().copied().collect(),
+                ..Default::default()
+            };
+            self.set_discovery_filter(filter)
+                .await
+                .wrap_err("Failed to set discovery filter")?;
+        }
+        let events = self
+            .discover_devices()
+            .await
+            .wrap_err("Failed to discover devices")?;
+        Ok(Box::pin(events.filter_map(|event| async move {
+            match event {
+                bluer::AdapterEvent::DeviceAdded(addr) => Some(addr),
+                _ => None,
+            }
+        })))
+    }
+
+    async fn device(&self, id: Address) -> Result<<Linux as Backend>::Device> {
+        self.device(id).wrap_err("Failed to get device from addr")
+    }
+
+    async fn remove_device(&self, id: Address) -> Result<()> {
+        self.remove_device(id)
+            .await
+            .wrap_err("Failed to remove device")
+    }
+}
+
+#[async_trait::async_trait]
+impl DeviceTrait<Linux> for bluer::Device {
+    async fn is_paired(&self) -> Result<bool> {
+        self.is_paired()
+            .await
+            .wrap_err("Failed to get device paired state")
+    }
+
+    async fn is_connected(&self) -> Result<bool> {
+        self.is_connected()
+            .await
+            .wrap_err("Failed to get device connected state")
+    }
+
+    async fn is_trusted(&self) -> Result<bool> {
+        self.is_trusted()
+            .await
+            .wrap_err("Failed to get device trusted state")
+    }
+
+    async fn alias(&self) -> Result<String> {
+        self.alias().await.wrap_err("Failed to get device alias")
+    }
+
+    async fn rssi(&self) -> Result<Option<i16>> {
+        self.rssi().await.wrap_err("Failed to get device rssi")
+    }
+
+    async fn events(&self) -> Result<<Linux as Backend>::DeviceEventStream> {
+        let events = self
+            .events()
+            .await
+            .wrap_err("Failed to get device events")?;
+        Ok(Box::pin(events))
+    }
+
+    async fn pair(&self) -> Result<()> {
+        self.pair().await.wrap_err("Failed to pair device")
+    }
+
+    async fn connect(&self) -> Result<()> {
+        self.connect().await.wrap_err("Failed to connect device")
+    }
+
+    async fn disconnect(&self) -> Result<()> {
+        self.disconnect()
+            .await
+            .wrap_err("Failed to disconnect device")
+    }
+
+    async fn set_trusted(&self, trusted: bool) -> Result<()> {
+        self.set_trusted(trusted)
+            .await
+            .wrap_err("Failed to set device trusted state")
+    }
+
+    async fn set_alias(&self, alias: String) -> Result<()> {
+        self.set_alias(alias)
+            .await
+            .wrap_err("Failed to set device alias")
+    }
+
+    async fn battery(&self) -> Result<Option<u8>> {
+        let Some(characteristic) = battery_characteristic(self).await? else {
+            return Ok(None);
+        };
+        let value = characteristic
+            .read()
+            .await
+            .wrap_err("Failed to read battery level")?;
+        Ok(value.first().copied())
+    }
+
+    async fn battery_notifications(&self) -> Result<Option<<Linux as Backend>::BatteryStream>> {
+        let Some(characteristic) = battery_characteristic(self).await? else {
+            return Ok(None);
+        };
+        let notifications = characteristic
+            .notify()
+            .await
+            .wrap_err("Failed to subscribe to battery notifications")?;
+        Ok(Some(Box::pin(notifications.filter_map(
+            |value| async move { value.first().copied() },
+        ))))
+    }
+}
+
+/// Look for the standard Battery Service's level characteristic among the
+/// device's already-resolved GATT services.
+async fn battery_characteristic(
+    device: &bluer::Device,
+) -> Result<Option<bluer::gatt::remote::Characteristic>> {
+    for service in device.services().await.wrap_err("Failed to get services")? {
+        if service
+            .uuid()
+            .await
+            .wrap_err("Failed to get service uuid")?
+            != super::battery_service::SERVICE
+        {
+            continue;
+        }
+        for characteristic in service
+            .characteristics()
+            .await
+            .wrap_err("Failed to get characteristics")?
+        {
+            if characteristic
+                .uuid()
+                .await
+                .wrap_err("Failed to get characteristic uuid")?
+                == super::battery_service::LEVEL_CHARACTERISTIC
+            {
+                return Ok(Some(characteristic));
+            }
+        }
+    }
+    Ok(None)
+}
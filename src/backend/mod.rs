@@ -0,0 +1,118 @@
+//! Abstraction over the platform's Bluetooth stack.
+//!
+//! `bluer` only talks to BlueZ over D-Bus, so it's Linux-only. [`Backend`] pulls the
+//! handful of operations the event loop actually needs (discovery, device lookup,
+//! pair/connect, property-change events) behind a trait, so [`crate::logic::run`] and
+//! [`crate::ui::App`] don't reference `bluer` types directly. [`linux::Linux`] wraps
+//! `bluer`; every other platform uses [`cross_platform::CrossPlatform`], which wraps
+//! the cross-platform `bluest` crate (CoreBluetooth on macOS, WinRT on Windows).
+use crate::config::{Config, Scan};
+use color_eyre::Result;
+use futures::Stream;
+use std::{fmt::Display, hash::Hash};
+
+#[cfg(target_os = "linux")]
+pub mod linux;
+#[cfg(target_os = "linux")]
+pub use linux::Linux as ActiveBackend;
+
+#[cfg(not(target_os = "linux"))]
+pub mod cross_platform;
+#[cfg(not(target_os = "linux"))]
+pub use cross_platform::CrossPlatform as ActiveBackend;
+
+/// A backend-normalized view of the property changes the event loop reacts to.
+///
+/// `bluer`'s `DeviceProperty` and whatever `bluest` reports both get collapsed down
+/// to this before reaching [`crate::logic::run`].
+pub enum PropertyChange {
+    Alias(String),
+    Connected(bool),
+    Paired(bool),
+    Trusted(bool),
+    Rssi(i16),
+}
+
+/// A controller available to manage, as shown in the adapter-selection list.
+#[derive(Debug, Clone)]
+pub struct AdapterInfo {
+    /// The name passed to [`Config::adapter`]/[`Backend::get_adapter`] to select
+    /// this controller.
+    pub name: String,
+    pub address: String,
+    pub powered: bool,
+}
+
+#[async_trait::async_trait]
+pub trait Backend: Sized + Send + Sync + 'static {
+    /// Opaque per-device identifier. BlueZ addresses devices by MAC `Address`;
+    /// `bluest` addresses them by an opaque `DeviceId`, so this can't just be an
+    /// address. It's the key type for `App.unpaired`/`paired` and the
+    /// spinner/changes maps.
+    type Id: Copy + Eq + Hash + Display + Send + Sync + 'static;
+    type Adapter: Adapter<Self> + Clone + Send + Sync + 'static;
+    type Device: Device<Self> + Send + Sync + 'static;
+    type DeviceEvent: Send + 'static;
+    type DeviceEventStream: Stream<Item = Self::DeviceEvent> + Send + Unpin + 'static;
+    type DiscoveryStream: Stream<Item = Self::Id> + Send + Unpin + 'static;
+    type BatteryStream: Stream<Item = u8> + Send + Unpin + 'static;
+
+    /// Grab the configured (or default) adapter and make sure it's powered on.
+    async fn get_adapter(config: &Config) -> Result<Self::Adapter>;
+
+    /// Normalize a raw device event into the subset the event loop understands,
+    /// discarding anything it doesn't handle (e.g. `bluer`'s ManufacturerData
+    /// updates).
+    fn normalize_event(event: Self::DeviceEvent) -> Option<PropertyChange>;
+
+    /// Parse an `Id` back out of its persisted `Display` form, for auto-reconnect
+    /// on startup. `None` if the backend's IDs don't round-trip through a string
+    /// (or the string is malformed).
+    fn parse_id(id: &str) -> Option<Self::Id>;
+
+    /// Enumerate the controllers available to manage, for the adapter-selection
+    /// list. A backend that can't see more than one controller just returns that
+    /// one.
+    async fn list_adapters() -> Result<Vec<AdapterInfo>>;
+}
+
+#[async_trait::async_trait]
+pub trait Adapter<B: Backend>: Sized {
+    /// Start scanning, honoring the configured service-UUID filter (if any).
+    async fn discover_devices(&self, scan: &Scan) -> Result<B::DiscoveryStream>;
+    async fn device(&self, id: B::Id) -> Result<B::Device>;
+    /// Forget a paired device outright (BlueZ's "remove"/unpair).
+    async fn remove_device(&self, id: B::Id) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+pub trait Device<B: Backend>: Sized + Send {
+    async fn is_paired(&self) -> Result<bool>;
+    async fn is_connected(&self) -> Result<bool>;
+    async fn is_trusted(&self) -> Result<bool>;
+    async fn alias(&self) -> Result<String>;
+    /// Current received signal strength, if the backend has a recent enough
+    /// advertisement to know it.
+    async fn rssi(&self) -> Result<Option<i16>>;
+    async fn events(&self) -> Result<B::DeviceEventStream>;
+    async fn pair(&self) -> Result<()>;
+    async fn connect(&self) -> Result<()>;
+    async fn disconnect(&self) -> Result<()>;
+    async fn set_trusted(&self, trusted: bool) -> Result<()>;
+    async fn set_alias(&self, alias: String) -> Result<()>;
+    /// Read the current level off the GATT Battery Service, if the device exposes
+    /// one. `None` means no battery service, not an error.
+    async fn battery(&self) -> Result<Option<u8>>;
+    /// Subscribe to battery level notifications, if the device exposes a Battery
+    /// Service. `None` means no battery service, not an error.
+    async fn battery_notifications(&self) -> Result<Option<B::BatteryStream>>;
+}
+
+/// Standard GATT Battery Service UUIDs (Bluetooth SIG-assigned), shared by every
+/// backend that reads battery level over GATT.
+pub mod battery_service {
+    use uuid::{uuid, Uuid};
+
+    pub const SERVICE: Uuid = uuid!("0000180f-0000-1000-8000-00805f9b34fb");
+    pub const LEVEL_CHARACTERISTIC: Uuid = uuid!("00002a19-0000-1000-8000-00805f9b34fb");
+}
@@ -0,0 +1,71 @@
+//! User-facing configuration, loaded once at startup from the platform config
+//! directory (e.g. `~/.config/bluey/config.toml` on Linux).
+use color_eyre::{eyre::Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Pin a specific adapter by name (e.g. `hci0`) instead of grabbing whatever
+    /// the platform considers the default.
+    pub adapter: Option<String>,
+    pub scan: Scan,
+    pub theme: Theme,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Scan {
+    /// Stop an in-progress scan after this many seconds. `None` keeps the scan
+    /// running until it is restarted (`s` or `:scan`) or the adapter changes.
+    pub duration_secs: Option<u64>,
+    /// Only surface devices advertising one of these service UUIDs.
+    pub service_uuids: Vec<Uuid>,
+}
+
+impl Scan {
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_secs.map(Duration::from_secs)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub error_border_color: String,
+    pub throbbers: Vec<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            error_border_color: "red".into(),
+            throbbers: ["│", "╱", "─", "╲"].map(String::from).to_vec(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn error_border_color(&self) -> Color {
+        self.error_border_color.parse().unwrap_or(Color::Red)
+    }
+}
+
+/// Load the config file if one exists, falling back to defaults if it doesn't.
+pub async fn load() -> Result<Config> {
+    let Some(dirs) = directories::ProjectDirs::from("", "", "bluey") else {
+        return Ok(Config::default());
+    };
+    let path = dirs.config_dir().join("config.toml");
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Config::default());
+        }
+        Err(error) => return Err(error).wrap_err("Failed to read config file"),
+    };
+    toml::from_str(&contents).wrap_err("Failed to parse config file")
+}
@@ -1,13 +1,23 @@
 use std::{collections::HashMap, time::Duration};
 
-use crate::{Device, Error, List, ui::App};
-use bluer::{Adapter, AdapterEvent, Address, DeviceEvent, DeviceProperty};
+use crate::{
+    backend::{Adapter, Backend, Device as BackendDevice, PropertyChange},
+    config::{Config, Scan},
+    store::Store,
+    ui::App,
+    Device, Error, List,
+};
 use color_eyre::{
+    eyre::{eyre, Context},
     Result,
-    eyre::{Context, eyre},
 };
 use crossterm::event::{Event, EventStream, KeyCode};
-use futures::stream::{SelectAll, StreamExt};
+use futures::{
+    future::OptionFuture,
+    stream::{SelectAll, StreamExt},
+    Stream,
+};
+use indexmap::IndexMap;
 use ratatui::DefaultTerminal;
 use tokio::{
     select,
@@ -20,14 +30,20 @@ use tracing::{debug, error, instrument, trace};
 const SPINNER_TICK: Duration = Duration::from_millis(100);
 
 #[instrument(skip_all)]
-pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
-    let mut app = App::default();
+pub async fn run<B: Backend>(
+    mut terminal: DefaultTerminal,
+    config: Config,
+    mut store: Store,
+) -> Result<()> {
+    let mut app = App::<B>::new(config.theme.clone());
 
-    let mut adapter: Option<Adapter> = None;
+    let mut adapter: Option<B::Adapter> = None;
     let (tx_adapter, mut rx_adapter) = mpsc::channel(1);
+    let initial_tx_adapter = tx_adapter.clone();
+    let adapter_config = config.clone();
     tokio::spawn(async move {
-        let result = get_adapter().await;
-        if let Err(error) = tx_adapter.send(result).await {
+        let result = B::get_adapter(&adapter_config).await;
+        if let Err(error) = initial_tx_adapter.send(result).await {
             error!(%error, "Failed to send adapter");
         }
     });
@@ -41,7 +57,12 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
     let (tx_errors, mut rx_errors) = mpsc::unbounded_channel();
     let (tx_spinner_tick, mut rx_spinner_tick) = mpsc::unbounded_channel();
     let (tx_complete, mut rx_complete) = mpsc::unbounded_channel();
-    let mut spinners: HashMap<Address, JoinHandle<_>> = HashMap::new();
+    let (tx_removed, mut rx_removed) = mpsc::unbounded_channel();
+    let (tx_battery, mut rx_battery) = mpsc::unbounded_channel();
+    let (tx_connected, mut rx_connected) = mpsc::unbounded_channel();
+    let (tx_adapter_list, mut rx_adapter_list) = mpsc::unbounded_channel();
+    let mut spinners: HashMap<B::Id, JoinHandle<()>> = HashMap::new();
+    let mut battery_monitors: HashMap<B::Id, JoinHandle<()>> = HashMap::new();
 
     loop {
         terminal
@@ -59,10 +80,9 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                 *index = index.wrapping_add(1);
             }
             Some(addr) = rx_complete.recv() => {
-                let Some(loading) = app.paired.get_mut(&addr).or_else(|| app.unpaired.get_mut(&addr)).map(|d| &mut d.loading) else {
-                    continue;
-                };
-                *loading = None;
+                if let Some(loading) = app.paired.get_mut(&addr).or_else(|| app.unpaired.get_mut(&addr)).map(|d| &mut d.loading) {
+                    *loading = None;
+                }
                 if let Some(h) = spinners.remove(&addr) {
                     h.abort();
                 }
@@ -70,11 +90,40 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
             Some(error) = rx_errors.recv() => {
                 app.error = Some(error);
             }
+            Some(addr) = rx_removed.recv() => {
+                app.unpaired.shift_remove(&addr);
+                app.paired.shift_remove(&addr);
+                if let Some(h) = battery_monitors.remove(&addr) {
+                    h.abort();
+                }
+            }
+            Some((addr, battery)) = rx_battery.recv() => {
+                app.paired.entry(addr).and_modify(|d| d.battery = Some(battery));
+            }
+            Some(addr) = rx_connected.recv() => {
+                if store.auto_reconnect.insert(addr.to_string()) {
+                    if let Some(device) = app.paired.get_mut(&addr) {
+                        device.auto_reconnect = true;
+                    }
+                    spawn_save_store(store.clone(), tx_errors.clone());
+                }
+            }
+            Some(result) = rx_adapter_list.recv() => {
+                match result {
+                    Ok(adapters) => app.adapters = Some((adapters, 0)),
+                    Err(error) => {
+                        app.error = Some(Error {
+                            message: error.to_string(),
+                            process: "listing adapters".into(),
+                        });
+                    }
+                }
+            }
             Some(addr) = rx_additions.recv() => {
                 let Some(adapter) = &adapter else {
                     continue;
                 };
-                let device = match adapter.device(addr) {
+                let device = match adapter.device(addr).await {
                     Ok(d) => d,
                     Err(error) => {
                         error!(%addr, %error, "Failed to get device from addr");
@@ -95,14 +144,21 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                         continue;
                     }
                 };
-                let events = match device.events().await {
+                let trusted = match device.is_trusted().await {
+                    Ok(trusted) => trusted,
+                    Err(error) => {
+                        error!(%addr, %error, "Failed to get device trusted state");
+                        continue;
+                    }
+                };
+                let device_events = match device.events().await {
                     Ok(events) => events,
                     Err(error) => {
                         error!(%addr, %error, "Failed to get device events");
                         continue;
                     }
                 };
-                changes.push(events.map(move |e| (addr, e)));
+                changes.push(device_events.map(move |e| (addr, e)));
                 let alias = match device.alias().await {
                     Ok(a) => a,
                     Err(error) => {
@@ -110,26 +166,77 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                         continue;
                     }
                 };
+                let rssi = match device.rssi().await {
+                    Ok(rssi) => rssi,
+                    Err(error) => {
+                        error!(%addr, %error, "Failed to get device rssi");
+                        continue;
+                    }
+                };
                 let device = Device {
                     alias,
                     connected,
+                    trusted,
+                    rssi,
+                    battery: None,
                     loading: None,
+                    auto_reconnect: store.auto_reconnect.contains(&addr.to_string()),
                 };
                 if paired {
                     app.paired.insert(addr, device);
+                    if connected {
+                        battery_monitors.insert(
+                            addr,
+                            spawn_battery_monitor::<B>(adapter.clone(), addr, tx_battery.clone()),
+                        );
+                    }
                 } else {
                     app.unpaired.insert(addr, device);
+                    if app.sort_by_rssi {
+                        sort_by_rssi(&mut app.unpaired);
+                    }
                 }
             }
-            Some((addr, DeviceEvent::PropertyChanged(property))) = changes.next() => {
+            Some((addr, event)) = changes.next() => {
+                let Some(property) = B::normalize_event(event) else {
+                    continue;
+                };
                 match property {
-                    DeviceProperty::Alias(alias) => {
-                        app.paired.entry(addr).and_modify(|d| d.alias = alias);
+                    PropertyChange::Alias(alias) => {
+                        if let Some(device) = app.paired.get_mut(&addr) {
+                            device.alias = alias;
+                        } else if let Some(device) = app.unpaired.get_mut(&addr) {
+                            device.alias = alias;
+                        }
                     }
-                    DeviceProperty::Connected(connected) => {
+                    PropertyChange::Connected(connected) => {
                         app.paired.entry(addr).and_modify(|d| d.connected = connected);
+                        if connected {
+                            if let Some(adapter) = &adapter {
+                                battery_monitors.insert(
+                                    addr,
+                                    spawn_battery_monitor::<B>(adapter.clone(), addr, tx_battery.clone()),
+                                );
+                            }
+                        } else {
+                            if let Some(h) = battery_monitors.remove(&addr) {
+                                h.abort();
+                            }
+                            app.paired.entry(addr).and_modify(|d| d.battery = None);
+                        }
+                    }
+                    PropertyChange::Trusted(trusted) => {
+                        app.paired.entry(addr).and_modify(|d| d.trusted = trusted);
+                    }
+                    PropertyChange::Rssi(rssi) => {
+                        if let Some(device) = app.unpaired.get_mut(&addr) {
+                            device.rssi = Some(rssi);
+                            if app.sort_by_rssi {
+                                sort_by_rssi(&mut app.unpaired);
+                            }
+                        }
                     }
-                    DeviceProperty::Paired(paired) => {
+                    PropertyChange::Paired(paired) => {
                         if paired {
                             let Some(device) = app.unpaired.shift_remove(&addr) else {
                                 continue;
@@ -142,12 +249,25 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                             app.unpaired.insert(addr, device);
                         }
                     }
-                    _ => {}
                 }
             }
             Some(fetched_adapter) = rx_adapter.recv() => {
                 let fetched_adapter = fetched_adapter?;
-                adapter_events_handle = Some(scan(fetched_adapter.clone(), tx_additions.clone()));
+                reset_adapter_state(
+                    &mut app,
+                    &mut adapter_events_handle,
+                    &mut spinners,
+                    &mut battery_monitors,
+                    &mut changes,
+                );
+                app.paired.clear();
+                adapter_events_handle = Some(scan::<B>(fetched_adapter.clone(), tx_additions.clone(), config.scan.clone()));
+                for id in &store.auto_reconnect {
+                    let Some(id) = B::parse_id(id) else {
+                        continue;
+                    };
+                    spawn_reconnect::<B>(fetched_adapter.clone(), id, tx_errors.clone());
+                }
                 adapter = Some(fetched_adapter);
             }
             Some(event) = events.next() => {
@@ -164,10 +284,93 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                     continue;
                 }
 
+                if let Some((adapters, selected)) = app.adapters.as_mut() {
+                    match event.code {
+                        KeyCode::Esc => {
+                            app.adapters = None;
+                        }
+                        KeyCode::Up => {
+                            *selected = selected.saturating_sub(1);
+                        }
+                        KeyCode::Down => {
+                            if *selected + 1 < adapters.len() {
+                                *selected += 1;
+                            }
+                        }
+                        KeyCode::Enter => {
+                            let Some(info) = adapters.get(*selected) else {
+                                app.adapters = None;
+                                continue;
+                            };
+                            let adapter_config = Config {
+                                adapter: Some(info.name.clone()),
+                                ..config.clone()
+                            };
+                            app.adapters = None;
+                            let tx_adapter = tx_adapter.clone();
+                            tokio::spawn(async move {
+                                let result = B::get_adapter(&adapter_config).await;
+                                if let Err(error) = tx_adapter.send(result).await {
+                                    error!(%error, "Failed to send adapter");
+                                }
+                            });
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                if let Some(input) = app.input.as_mut() {
+                    match event.code {
+                        KeyCode::Esc => {
+                            app.input = None;
+                        }
+                        KeyCode::Enter => {
+                            let command = app.input.take().unwrap_or_default();
+                            execute_command::<B, _>(
+                                &command,
+                                &mut app,
+                                adapter.as_ref(),
+                                &tx_errors,
+                                &tx_spinner_tick,
+                                &tx_complete,
+                                &tx_connected,
+                                &mut spinners,
+                                &mut battery_monitors,
+                                &tx_additions,
+                                &config.scan,
+                                &mut adapter_events_handle,
+                                &mut changes,
+                            )
+                            .await;
+                        }
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Char(c) => {
+                            input.push(c);
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
                 match event.code {
                     KeyCode::Char('q') => {
                         break Ok(());
                     }
+                    KeyCode::Char(':') => {
+                        app.input = Some(String::new());
+                    }
+                    KeyCode::Char('a') => {
+                        let tx_adapter_list = tx_adapter_list.clone();
+                        tokio::spawn(async move {
+                            let result = B::list_adapters().await;
+                            if let Err(error) = tx_adapter_list.send(result) {
+                                error!(%error, "Failed to send adapter list");
+                            }
+                        });
+                    }
                     KeyCode::Down => {
                         let len = match app.selected_list {
                             List::Unpaired => app.unpaired.len(),
@@ -196,12 +399,14 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                         let Some(adapter) = adapter.clone() else {
                             continue;
                         };
-                        if let Some(device_events_handle) = &adapter_events_handle {
-                            device_events_handle.abort()
-                        }
-                        app.unpaired.clear();
-                        app.paired.clear();
-                        adapter_events_handle = Some(scan(adapter, tx_additions.clone()));
+                        reset_adapter_state(
+                            &mut app,
+                            &mut adapter_events_handle,
+                            &mut spinners,
+                            &mut battery_monitors,
+                            &mut changes,
+                        );
+                        adapter_events_handle = Some(scan::<B>(adapter, tx_additions.clone(), config.scan.clone()));
                     }
                     KeyCode::Enter => {
                         let slice = match app.selected_list {
@@ -228,7 +433,7 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                             List::Paired => format!("connecting to {alias}"),
                             List::Unpaired => format!("pairing with {alias}"),
                         };
-                        let device = match adapter.device(addr) {
+                        let device = match adapter.device(addr).await {
                             Ok(d) => d,
                             Err(error) => {
                                 app.error = Some(Error {
@@ -259,17 +464,25 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                                 });
                             }
                             List::Paired => {
+                                let tx_connected = tx_connected.clone();
                                 tokio::spawn(async move {
                                     let res = device.connect().await;
                                     if let Err(error) = tx_complete.send(addr) {
                                         error!(%addr, %error, "Failed to send connect complete");
                                     }
-                                    if let Err(error) = res {
-                                        if let Err(error) = tx_errors.send(Error {
-                                            message: error.to_string(),
-                                            process,
-                                        }) {
-                                            error!(%error, "Failed to send connect error");
+                                    match res {
+                                        Ok(()) => {
+                                            if let Err(error) = tx_connected.send(addr) {
+                                                error!(%addr, %error, "Failed to send connected event");
+                                            }
+                                        }
+                                        Err(error) => {
+                                            if let Err(error) = tx_errors.send(Error {
+                                                message: error.to_string(),
+                                                process,
+                                            }) {
+                                                error!(%error, "Failed to send connect error");
+                                            }
                                         }
                                     }
                                 });
@@ -284,6 +497,205 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
                             }
                         }));
                     }
+                    KeyCode::Char('o') => {
+                        app.sort_by_rssi = !app.sort_by_rssi;
+                        if app.sort_by_rssi {
+                            sort_by_rssi(&mut app.unpaired);
+                        }
+                    }
+                    KeyCode::Char('d') => {
+                        if app.selected_list != List::Paired {
+                            continue;
+                        }
+                        let Some(adapter) = &adapter else {
+                            continue;
+                        };
+                        let Some((addr, Device { alias, loading, .. })) =
+                            app.paired.get_index_mut(app.selected_row)
+                        else {
+                            continue;
+                        };
+                        if loading.is_some() {
+                            continue;
+                        }
+                        *loading = Some(0);
+                        let addr = *addr;
+                        let alias = alias.clone();
+                        let device = match adapter.device(addr).await {
+                            Ok(d) => d,
+                            Err(error) => {
+                                app.error = Some(Error {
+                                    message: error.to_string(),
+                                    process: format!("disconnecting from {alias}"),
+                                });
+                                continue;
+                            }
+                        };
+                        let tx_errors = tx_errors.clone();
+                        let tx_complete = tx_complete.clone();
+                        let tx_spinner_tick = tx_spinner_tick.clone();
+                        tokio::spawn(async move {
+                            let res = device.disconnect().await;
+                            if let Err(error) = tx_complete.send(addr) {
+                                error!(%addr, %error, "Failed to send disconnect complete");
+                            }
+                            if let Err(error) = res {
+                                if let Err(error) = tx_errors.send(Error {
+                                    message: error.to_string(),
+                                    process: format!("disconnecting from {alias}"),
+                                }) {
+                                    error!(%error, "Failed to send disconnect error");
+                                }
+                            }
+                        });
+                        spinners.insert(
+                            addr,
+                            tokio::spawn(async move {
+                                loop {
+                                    sleep(SPINNER_TICK).await;
+                                    if let Err(error) = tx_spinner_tick.send(addr) {
+                                        error!(%addr, %error, "Failed to send spinner tick");
+                                    }
+                                }
+                            }),
+                        );
+                    }
+                    KeyCode::Char('r') => {
+                        if app.selected_list != List::Paired {
+                            continue;
+                        }
+                        let Some(adapter) = &adapter else {
+                            continue;
+                        };
+                        let Some((addr, Device { alias, loading, .. })) =
+                            app.paired.get_index_mut(app.selected_row)
+                        else {
+                            continue;
+                        };
+                        if loading.is_some() {
+                            continue;
+                        }
+                        *loading = Some(0);
+                        let addr = *addr;
+                        let alias = alias.clone();
+                        let adapter = adapter.clone();
+                        let tx_errors = tx_errors.clone();
+                        let tx_removed = tx_removed.clone();
+                        let tx_complete = tx_complete.clone();
+                        let tx_spinner_tick = tx_spinner_tick.clone();
+                        tokio::spawn(async move {
+                            let res = adapter.remove_device(addr).await;
+                            if let Err(error) = tx_complete.send(addr) {
+                                error!(%addr, %error, "Failed to send remove complete");
+                            }
+                            match res {
+                                Ok(()) => {
+                                    if let Err(error) = tx_removed.send(addr) {
+                                        error!(%addr, %error, "Failed to send remove complete");
+                                    }
+                                }
+                                Err(error) => {
+                                    if let Err(error) = tx_errors.send(Error {
+                                        message: error.to_string(),
+                                        process: format!("removing {alias}"),
+                                    }) {
+                                        error!(%error, "Failed to send remove error");
+                                    }
+                                }
+                            }
+                        });
+                        spinners.insert(
+                            addr,
+                            tokio::spawn(async move {
+                                loop {
+                                    sleep(SPINNER_TICK).await;
+                                    if let Err(error) = tx_spinner_tick.send(addr) {
+                                        error!(%addr, %error, "Failed to send spinner tick");
+                                    }
+                                }
+                            }),
+                        );
+                    }
+                    KeyCode::Char('t') => {
+                        if app.selected_list != List::Paired {
+                            continue;
+                        }
+                        let Some(adapter) = &adapter else {
+                            continue;
+                        };
+                        let Some((addr, device)) = app.paired.get_index_mut(app.selected_row)
+                        else {
+                            continue;
+                        };
+                        if device.loading.is_some() {
+                            continue;
+                        }
+                        device.loading = Some(0);
+                        let addr = *addr;
+                        let alias = device.alias.clone();
+                        let trusted = !device.trusted;
+                        let process = if trusted {
+                            format!("trusting {alias}")
+                        } else {
+                            format!("untrusting {alias}")
+                        };
+                        let device = match adapter.device(addr).await {
+                            Ok(d) => d,
+                            Err(error) => {
+                                app.error = Some(Error {
+                                    message: error.to_string(),
+                                    process,
+                                });
+                                continue;
+                            }
+                        };
+                        let tx_errors = tx_errors.clone();
+                        let tx_complete = tx_complete.clone();
+                        let tx_spinner_tick = tx_spinner_tick.clone();
+                        tokio::spawn(async move {
+                            let res = device.set_trusted(trusted).await;
+                            if let Err(error) = tx_complete.send(addr) {
+                                error!(%addr, %error, "Failed to send trust complete");
+                            }
+                            if let Err(error) = res {
+                                if let Err(error) = tx_errors.send(Error {
+                                    message: error.to_string(),
+                                    process,
+                                }) {
+                                    error!(%error, "Failed to send trust error");
+                                }
+                            }
+                        });
+                        spinners.insert(
+                            addr,
+                            tokio::spawn(async move {
+                                loop {
+                                    sleep(SPINNER_TICK).await;
+                                    if let Err(error) = tx_spinner_tick.send(addr) {
+                                        error!(%addr, %error, "Failed to send spinner tick");
+                                    }
+                                }
+                            }),
+                        );
+                    }
+                    KeyCode::Char('p') => {
+                        let slice = match app.selected_list {
+                            List::Unpaired => app.unpaired.as_mut_slice(),
+                            List::Paired => app.paired.as_mut_slice(),
+                        };
+                        let Some((&addr, device)) = slice.get_index_mut(app.selected_row) else {
+                            continue;
+                        };
+                        device.auto_reconnect = !device.auto_reconnect;
+                        let changed = if device.auto_reconnect {
+                            store.auto_reconnect.insert(addr.to_string())
+                        } else {
+                            store.auto_reconnect.remove(&addr.to_string())
+                        };
+                        if changed {
+                            spawn_save_store(store.clone(), tx_errors.clone());
+                        }
+                    }
                     KeyCode::Esc => {
                         app.error.take();
                     }
@@ -294,42 +706,539 @@ pub async fn run(mut terminal: DefaultTerminal) -> Result<()> {
     }
 }
 
+/// Sort by descending signal strength, so the strongest/nearest devices float to
+/// the top; devices with no RSSI reading yet sink to the bottom.
+fn sort_by_rssi<Id: std::hash::Hash + Eq>(map: &mut IndexMap<Id, Device>) {
+    map.sort_by(|_, a, _, b| b.rssi.cmp(&a.rssi));
+}
+
+/// Look up a device by alias (case-insensitive) in either list, falling back to
+/// parsing `target` as a raw backend address/ID.
+fn resolve<B: Backend>(app: &App<B>, target: &str) -> Option<B::Id> {
+    app.paired
+        .iter()
+        .chain(app.unpaired.iter())
+        .find(|(_, device)| device.alias.eq_ignore_ascii_case(target))
+        .map(|(&id, _)| id)
+        .or_else(|| B::parse_id(target))
+}
+
+/// Parse and run a single line typed into command mode (`:connect <addr>`, etc.).
+/// `pair` and `connect` show the loading spinner and report completion through
+/// `tx_complete` like the equivalent keybindings; `disconnect` and `alias` run in
+/// the background and report only errors through `tx_errors`, with no spinner.
 #[instrument(skip_all)]
-fn scan(adapter: Adapter, tx_additions: UnboundedSender<Address>) -> JoinHandle<()> {
+#[allow(clippy::too_many_arguments)]
+async fn execute_command<B: Backend, S>(
+    command: &str,
+    app: &mut App<B>,
+    adapter: Option<&B::Adapter>,
+    tx_errors: &UnboundedSender<Error>,
+    tx_spinner_tick: &UnboundedSender<B::Id>,
+    tx_complete: &UnboundedSender<B::Id>,
+    tx_connected: &UnboundedSender<B::Id>,
+    spinners: &mut HashMap<B::Id, JoinHandle<()>>,
+    battery_monitors: &mut HashMap<B::Id, JoinHandle<()>>,
+    tx_additions: &UnboundedSender<B::Id>,
+    scan_config: &Scan,
+    adapter_events_handle: &mut Option<JoinHandle<()>>,
+    changes: &mut SelectAll<S>,
+) where
+    S: Stream + Send + Unpin + 'static,
+{
+    let process = "running command".to_string();
+    let mut parts = command.split_whitespace();
+    let Some(verb) = parts.next() else {
+        return;
+    };
+
+    if verb == "scan" {
+        let Some(adapter) = adapter else {
+            app.error = Some(Error {
+                message: "No adapter available yet".into(),
+                process,
+            });
+            return;
+        };
+        reset_adapter_state(
+            app,
+            adapter_events_handle,
+            spinners,
+            battery_monitors,
+            changes,
+        );
+        *adapter_events_handle = Some(scan::<B>(
+            adapter.clone(),
+            tx_additions.clone(),
+            scan_config.clone(),
+        ));
+        return;
+    }
+
+    let Some(target) = parts.next() else {
+        app.error = Some(Error {
+            message: format!("`{verb}` requires a device address or alias"),
+            process,
+        });
+        return;
+    };
+
+    let Some(adapter) = adapter else {
+        app.error = Some(Error {
+            message: "No adapter available yet".into(),
+            process,
+        });
+        return;
+    };
+
+    let Some(addr) = resolve(app, target) else {
+        app.error = Some(Error {
+            message: format!("No device matches `{target}`"),
+            process,
+        });
+        return;
+    };
+
+    let handle = match adapter.device(addr).await {
+        Ok(d) => d,
+        Err(error) => {
+            app.error = Some(Error {
+                message: error.to_string(),
+                process: format!("resolving {target}"),
+            });
+            return;
+        }
+    };
+
+    let alias = app
+        .unpaired
+        .get(&addr)
+        .or_else(|| app.paired.get(&addr))
+        .map(|d| d.alias.clone())
+        .unwrap_or_else(|| target.to_string());
+
+    match verb {
+        "pair" | "connect" => {
+            let entry = app
+                .unpaired
+                .get_mut(&addr)
+                .or_else(|| app.paired.get_mut(&addr));
+            if let Some(device) = entry {
+                if device.loading.is_some() {
+                    return;
+                }
+                device.loading = Some(0);
+                let tx_spinner_tick = tx_spinner_tick.clone();
+                spinners.insert(
+                    addr,
+                    tokio::spawn(async move {
+                        loop {
+                            sleep(SPINNER_TICK).await;
+                            if let Err(error) = tx_spinner_tick.send(addr) {
+                                error!(%addr, %error, "Failed to send spinner tick");
+                            }
+                        }
+                    }),
+                );
+            }
+            let pair = verb == "pair";
+            let process = if pair {
+                format!("pairing with {alias}")
+            } else {
+                format!("connecting to {alias}")
+            };
+            let tx_errors = tx_errors.clone();
+            let tx_complete = tx_complete.clone();
+            let tx_connected = tx_connected.clone();
+            tokio::spawn(async move {
+                let res = if pair {
+                    handle.pair().await
+                } else {
+                    handle.connect().await
+                };
+                if let Err(error) = tx_complete.send(addr) {
+                    error!(%addr, %error, "Failed to send command complete");
+                }
+                match res {
+                    Ok(()) => {
+                        if !pair {
+                            if let Err(error) = tx_connected.send(addr) {
+                                error!(%addr, %error, "Failed to send connected event");
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        if let Err(error) = tx_errors.send(Error {
+                            message: error.to_string(),
+                            process,
+                        }) {
+                            error!(%error, "Failed to send command error");
+                        }
+                    }
+                }
+            });
+        }
+        "disconnect" => {
+            let tx_errors = tx_errors.clone();
+            tokio::spawn(async move {
+                if let Err(error) = handle.disconnect().await {
+                    if let Err(error) = tx_errors.send(Error {
+                        message: error.to_string(),
+                        process: format!("disconnecting from {alias}"),
+                    }) {
+                        error!(%error, "Failed to send disconnect error");
+                    }
+                }
+            });
+        }
+        "alias" => {
+            let new_alias = parts.collect::<Vec<_>>().join(" ");
+            if new_alias.is_empty() {
+                app.error = Some(Error {
+                    message: "`alias` requires a new name".into(),
+                    process,
+                });
+                return;
+            }
+            let tx_errors = tx_errors.clone();
+            tokio::spawn(async move {
+                if let Err(error) = handle.set_alias(new_alias).await {
+                    if let Err(error) = tx_errors.send(Error {
+                        message: error.to_string(),
+                        process: format!("renaming {alias}"),
+                    }) {
+                        error!(%error, "Failed to send alias error");
+                    }
+                }
+            });
+        }
+        _ => {
+            app.error = Some(Error {
+                message: format!("Unknown command `{verb}`"),
+                process,
+            });
+        }
+    }
+}
+
+/// Persist the device store in the background, surfacing a failure the same way
+/// a user-initiated action would.
+#[instrument(skip_all)]
+fn spawn_save_store(store: Store, tx_errors: UnboundedSender<Error>) {
+    tokio::spawn(async move {
+        if let Err(error) = store.save().await {
+            if let Err(error) = tx_errors.send(Error {
+                message: error.to_string(),
+                process: "saving auto-reconnect settings".into(),
+            }) {
+                error!(%error, "Failed to send store save error");
+            }
+        }
+    });
+}
+
+/// Try to reconnect to a device remembered from a previous session. Runs once,
+/// in the background, as soon as the adapter is ready; the device's normal entry
+/// in `App` is populated the usual way once discovery notices it.
+#[instrument(skip_all)]
+fn spawn_reconnect<B: Backend>(adapter: B::Adapter, id: B::Id, tx_errors: UnboundedSender<Error>) {
     tokio::spawn(async move {
-        let mut events = match adapter.discover_devices().await {
+        let device = match adapter.device(id).await {
+            Ok(device) => device,
+            Err(error) => {
+                error!(%id, %error, "Failed to get device for auto-reconnect");
+                return;
+            }
+        };
+        if let Err(error) = device.connect().await {
+            if let Err(error) = tx_errors.send(Error {
+                message: error.to_string(),
+                process: format!("auto-reconnecting to {id}"),
+            }) {
+                error!(%id, %error, "Failed to send auto-reconnect error");
+            }
+        }
+    });
+}
+
+/// Read the initial battery level (if any) and then follow notifications for as
+/// long as the device stays connected, pushing each reading to `tx_battery`.
+#[instrument(skip_all)]
+fn spawn_battery_monitor<B: Backend>(
+    adapter: B::Adapter,
+    addr: B::Id,
+    tx_battery: UnboundedSender<(B::Id, u8)>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let device = match adapter.device(addr).await {
+            Ok(device) => device,
+            Err(error) => {
+                error!(%addr, %error, "Failed to get device for battery monitor");
+                return;
+            }
+        };
+        match device.battery().await {
+            Ok(Some(level)) => {
+                if let Err(error) = tx_battery.send((addr, level)) {
+                    error!(%addr, %error, "Failed to send initial battery level");
+                }
+            }
+            Ok(None) => {}
+            Err(error) => error!(%addr, %error, "Failed to read battery level"),
+        }
+        let notifications = match device.battery_notifications().await {
+            Ok(Some(notifications)) => notifications,
+            Ok(None) => return,
+            Err(error) => {
+                error!(%addr, %error, "Failed to subscribe to battery notifications");
+                return;
+            }
+        };
+        let mut notifications = notifications;
+        while let Some(level) = notifications.next().await {
+            if let Err(error) = tx_battery.send((addr, level)) {
+                error!(%addr, %error, "Failed to send battery level");
+                break;
+            }
+        }
+    })
+}
+
+/// Tear down everything tied to the previously-active adapter before switching
+/// controllers or re-scanning: abort the discovery task, every per-device
+/// spinner and battery monitor, and drop the property-change stream, then clear
+/// the unpaired list (devices only ever show up there via a discovery event, so
+/// they need to be rediscovered). Without this, tasks and events from the old
+/// adapter keep running and can land a stale `PropertyChange` against a
+/// same-`Id` entry that's now being tracked on the new one.
+///
+/// This does NOT clear `app.paired` — a rescan doesn't mean already-paired
+/// devices got unpaired, and BlueZ/`bluest` aren't guaranteed to re-emit a
+/// discovery event for a device that's already connected and has stopped
+/// advertising. Callers that are actually switching adapters (where the paired
+/// list legitimately needs to reflect the new controller) clear it themselves.
+fn reset_adapter_state<B: Backend, S>(
+    app: &mut App<B>,
+    adapter_events_handle: &mut Option<JoinHandle<()>>,
+    spinners: &mut HashMap<B::Id, JoinHandle<()>>,
+    battery_monitors: &mut HashMap<B::Id, JoinHandle<()>>,
+    changes: &mut SelectAll<S>,
+) where
+    S: Stream + Send + Unpin + 'static,
+{
+    if let Some(handle) = adapter_events_handle.take() {
+        handle.abort();
+    }
+    for (_, handle) in spinners.drain() {
+        handle.abort();
+    }
+    for (_, handle) in battery_monitors.drain() {
+        handle.abort();
+    }
+    *changes = SelectAll::new();
+    app.unpaired.clear();
+}
+
+#[instrument(skip_all)]
+fn scan<B: Backend>(
+    adapter: B::Adapter,
+    tx_additions: UnboundedSender<B::Id>,
+    scan_config: Scan,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut events = match adapter.discover_devices(&scan_config).await {
             Ok(events) => events,
             Err(error) => {
                 error!(%error, "Failed to discover devices");
                 return;
             }
         };
-        while let Some(event) = events.next().await {
-            let AdapterEvent::DeviceAdded(addr) = event else {
-                continue;
-            };
-            if let Err(error) = tx_additions.send(addr) {
-                error!(%addr, %error, "Failed to send device addition event");
+        let timeout: OptionFuture<_> = scan_config.duration().map(sleep).into();
+        tokio::pin!(timeout);
+        loop {
+            select! {
+                addr = events.next() => {
+                    let Some(addr) = addr else { break };
+                    if let Err(error) = tx_additions.send(addr) {
+                        error!(%addr, %error, "Failed to send device addition event");
+                    }
+                }
+                Some(()) = &mut timeout => {
+                    debug!("Scan timed out");
+                    break;
+                }
             }
         }
     })
 }
 
-#[instrument]
-async fn get_adapter() -> Result<bluer::Adapter> {
-    trace!("Getting session");
-    let session = bluer::Session::new()
-        .await
-        .wrap_err("Failed to create session")?;
-    trace!("Getting default adapter");
-    let adapter = session
-        .default_adapter()
-        .await
-        .wrap_err("Failed to get default adapter")?;
-    trace!("Turning on adapter");
-    adapter
-        .set_powered(true)
-        .await
-        .wrap_err("Failed to turn on adapter")?;
-    Ok(adapter)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::{Adapter as AdapterTrait, AdapterInfo, Device as DeviceTrait},
+        config::Theme,
+    };
+    use futures::stream;
+    use std::{fmt, pin::Pin};
+
+    fn device(alias: &str, rssi: Option<i16>) -> Device {
+        Device {
+            alias: alias.to_string(),
+            connected: false,
+            trusted: false,
+            rssi,
+            battery: None,
+            loading: None,
+            auto_reconnect: false,
+        }
+    }
+
+    #[test]
+    fn sort_by_rssi_strongest_first_none_sinks_to_bottom() {
+        let mut map: IndexMap<u8, Device> = IndexMap::new();
+        map.insert(1, device("weak", Some(-80)));
+        map.insert(2, device("unknown", None));
+        map.insert(3, device("strong", Some(-40)));
+        sort_by_rssi(&mut map);
+        let aliases: Vec<_> = map.values().map(|d| d.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["strong", "weak", "unknown"]);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct TestId(u8);
+
+    impl fmt::Display for TestId {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    struct TestBackend;
+
+    #[derive(Clone)]
+    struct TestAdapter;
+
+    struct TestDevice;
+
+    #[async_trait::async_trait]
+    impl Backend for TestBackend {
+        type Id = TestId;
+        type Adapter = TestAdapter;
+        type Device = TestDevice;
+        type DeviceEvent = ();
+        type DeviceEventStream = Pin<Box<dyn Stream<Item = ()> + Send>>;
+        type DiscoveryStream = Pin<Box<dyn Stream<Item = TestId> + Send>>;
+        type BatteryStream = Pin<Box<dyn Stream<Item = u8> + Send>>;
+
+        async fn get_adapter(_config: &Config) -> Result<Self::Adapter> {
+            unimplemented!()
+        }
+
+        fn normalize_event(_event: ()) -> Option<PropertyChange> {
+            unimplemented!()
+        }
+
+        fn parse_id(id: &str) -> Option<TestId> {
+            id.parse::<u8>().ok().map(TestId)
+        }
+
+        async fn list_adapters() -> Result<Vec<AdapterInfo>> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AdapterTrait<TestBackend> for TestAdapter {
+        async fn discover_devices(
+            &self,
+            _scan: &Scan,
+        ) -> Result<<TestBackend as Backend>::DiscoveryStream> {
+            Ok(Box::pin(stream::empty()))
+        }
+
+        async fn device(&self, _id: TestId) -> Result<TestDevice> {
+            unimplemented!()
+        }
+
+        async fn remove_device(&self, _id: TestId) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DeviceTrait<TestBackend> for TestDevice {
+        async fn is_paired(&self) -> Result<bool> {
+            unimplemented!()
+        }
+
+        async fn is_connected(&self) -> Result<bool> {
+            unimplemented!()
+        }
+
+        async fn is_trusted(&self) -> Result<bool> {
+            unimplemented!()
+        }
+
+        async fn alias(&self) -> Result<String> {
+            unimplemented!()
+        }
+
+        async fn rssi(&self) -> Result<Option<i16>> {
+            unimplemented!()
+        }
+
+        async fn events(&self) -> Result<<TestBackend as Backend>::DeviceEventStream> {
+            unimplemented!()
+        }
+
+        async fn pair(&self) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn connect(&self) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn disconnect(&self) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn set_trusted(&self, _trusted: bool) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn set_alias(&self, _alias: String) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn battery(&self) -> Result<Option<u8>> {
+            unimplemented!()
+        }
+
+        async fn battery_notifications(
+            &self,
+        ) -> Result<Option<<TestBackend as Backend>::BatteryStream>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn resolve_matches_alias_case_insensitively() {
+        let mut app = App::<TestBackend>::new(Theme::default());
+        app.paired.insert(TestId(1), device("My Headphones", None));
+        assert_eq!(resolve(&app, "my headphones"), Some(TestId(1)));
+    }
+
+    #[test]
+    fn resolve_falls_back_to_parsing_target_as_id() {
+        let app = App::<TestBackend>::new(Theme::default());
+        assert_eq!(resolve(&app, "7"), Some(TestId(7)));
+    }
+
+    #[test]
+    fn resolve_returns_none_when_nothing_matches() {
+        let app = App::<TestBackend>::new(Theme::default());
+        assert_eq!(resolve(&app, "nope"), None);
+    }
 }
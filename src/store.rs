@@ -0,0 +1,47 @@
+//! Persisted set of devices the user has pinned for auto-reconnect, so `run()`
+//! can try to bring them back up in the background on the next launch.
+use color_eyre::{eyre::Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, path::PathBuf};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Store {
+    /// Backend device IDs (via `Display`) the user has pinned to auto-reconnect.
+    pub auto_reconnect: BTreeSet<String>,
+}
+
+impl Store {
+    pub async fn load() -> Result<Self> {
+        let Some(path) = path() else {
+            return Ok(Self::default());
+        };
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(error) => return Err(error).wrap_err("Failed to read device store"),
+        };
+        toml::from_str(&contents).wrap_err("Failed to parse device store")
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        let Some(path) = path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .wrap_err("Failed to create state directory")?;
+        }
+        let contents = toml::to_string_pretty(self).wrap_err("Failed to serialize device store")?;
+        tokio::fs::write(&path, contents)
+            .await
+            .wrap_err("Failed to write device store")
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "bluey")
+        .map(|dirs| dirs.data_dir().join("known_devices.toml"))
+}
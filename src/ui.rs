@@ -1,28 +1,89 @@
-use bluer::Address;
 use indexmap::IndexMap;
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Row, Table, TableState},
+    widgets::{Block, Borders, List as ListWidget, ListItem, ListState, Row, Table, TableState},
 };
 use tui_popup::Popup;
 
-use crate::{Device, Error, List};
+use crate::{
+    backend::{AdapterInfo, Backend},
+    config::Theme,
+    Device, Error, List,
+};
 
-const THROBBERS: [&str; 4] = ["│", "╱", "─", "╲"];
+/// A small signal-quality bar alongside the raw dBm reading, e.g. `▂▄▆█ -52`.
+/// Blank when a device hasn't reported an RSSI yet (e.g. not yet backed by an
+/// advertisement, or the backend doesn't expose one at all).
+fn rssi_bar(rssi: Option<i16>) -> String {
+    let Some(rssi) = rssi else {
+        return String::new();
+    };
+    let bar = match rssi {
+        r if r >= -60 => "▂▄▆█",
+        r if r >= -70 => "▂▄▆░",
+        r if r >= -80 => "▂▄░░",
+        r if r >= -90 => "▂░░░",
+        _ => "░░░░",
+    };
+    format!("{bar} {rssi}")
+}
 
-#[derive(Default)]
-pub struct App {
+pub struct App<B: Backend> {
     pub selected_list: List,
     pub selected_row: usize,
-    pub unpaired: IndexMap<Address, Device>,
-    pub paired: IndexMap<Address, Device>,
+    pub unpaired: IndexMap<B::Id, Device>,
+    pub paired: IndexMap<B::Id, Device>,
     pub error: Option<Error>,
+    pub sort_by_rssi: bool,
+    pub theme: Theme,
+    /// The command-line buffer, when the user has activated command mode with
+    /// `:`. `None` means command mode is inactive.
+    pub input: Option<String>,
+    /// The adapter-selection list and the currently-highlighted row, when the
+    /// user has activated it with `a`. `None` means the device view is showing.
+    pub adapters: Option<(Vec<AdapterInfo>, usize)>,
 }
 
-impl App {
+impl<B: Backend> App<B> {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            selected_list: List::default(),
+            selected_row: 0,
+            unpaired: IndexMap::new(),
+            paired: IndexMap::new(),
+            error: None,
+            sort_by_rssi: false,
+            theme,
+            input: None,
+            adapters: None,
+        }
+    }
+
     pub fn render(&self, frame: &mut Frame) {
         let [top, bottom] =
             Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+
+        if let Some((adapters, selected)) = &self.adapters {
+            let items = adapters.iter().map(|adapter| {
+                let powered = if adapter.powered { "on" } else { "off" };
+                ListItem::new(format!(
+                    "{}  {}  [{powered}]",
+                    adapter.name, adapter.address
+                ))
+            });
+            let list = ListWidget::new(items)
+                .block(Block::default().title("Adapters").borders(Borders::ALL))
+                .highlight_style(Style::default().reversed());
+            let mut state = ListState::default().with_selected(Some(*selected));
+            frame.render_stateful_widget(list, top, &mut state);
+
+            let legend = Text::raw("▼▲: navigate • ↵: select • esc: cancel")
+                .alignment(Alignment::Center)
+                .style(Style::default().bold());
+            frame.render_widget(legend, bottom);
+            return;
+        }
+
         let [left, right] = Layout::horizontal([Constraint::Ratio(1, 2); 2]).areas(top);
 
         let (unpaired, mut unpaired_state) =
@@ -31,31 +92,53 @@ impl App {
         let (paired, mut paired_state) = self.table(self.paired.values().collect(), List::Paired);
         frame.render_stateful_widget(paired, right, &mut paired_state);
 
-        let legend_items: &[(&str, &str)] = if self.error.is_some() {
-            &[("esc", "close")]
+        let legend = if let Some(input) = &self.input {
+            Text::raw(format!(":{input}")).alignment(Alignment::Left)
         } else {
-            &[
-                ("◀▼▲▶", "navigate"),
-                ("q", "quit"),
-                ("s", "scan"),
-                (
-                    "↵",
-                    match self.selected_list {
-                        List::Unpaired => "pair",
-                        List::Paired => "connect",
-                    },
-                ),
-            ]
-        };
+            let legend_items: Vec<(&str, &str)> = if self.error.is_some() {
+                vec![("esc", "close")]
+            } else {
+                let mut items = vec![
+                    ("◀▼▲▶", "navigate"),
+                    ("q", "quit"),
+                    ("s", "scan"),
+                    (
+                        "↵",
+                        match self.selected_list {
+                            List::Unpaired => "pair",
+                            List::Paired => "connect",
+                        },
+                    ),
+                    ("p", "pin for auto-reconnect"),
+                    (":", "command"),
+                    ("a", "adapters"),
+                ];
+                if self.selected_list == List::Paired {
+                    items.push(("d", "disconnect"));
+                    items.push(("r", "remove"));
+                    items.push(("t", "toggle trust"));
+                } else {
+                    items.push((
+                        "o",
+                        if self.sort_by_rssi {
+                            "sort: signal"
+                        } else {
+                            "sort: discovery order"
+                        },
+                    ));
+                }
+                items
+            };
 
-        let legend = Text::raw(
-            legend_items
-                .iter()
-                .map(|(key, action)| format!("{key}: {action}"))
-                .collect::<Vec<_>>()
-                .join(" • "),
-        )
-        .alignment(Alignment::Center)
+            Text::raw(
+                legend_items
+                    .iter()
+                    .map(|(key, action)| format!("{key}: {action}"))
+                    .collect::<Vec<_>>()
+                    .join(" • "),
+            )
+            .alignment(Alignment::Center)
+        }
         .style(Style::default().bold());
         frame.render_widget(legend, bottom);
 
@@ -68,7 +151,7 @@ impl App {
         ]);
         let popup = Popup::new(text)
             .title("Error")
-            .border_style(Style::default().red());
+            .border_style(Style::default().fg(self.theme.error_border_color()));
         frame.render_widget(&popup, frame.area());
     }
 
@@ -83,19 +166,39 @@ impl App {
                 .border_set(symbols::border::THICK);
         }
         let rows = items.iter().map(|device| {
-            let mut cells = vec![device.alias.clone()];
-            if let List::Paired = list {
-                cells.push(device.connected.to_string());
+            let alias = if device.auto_reconnect {
+                format!("\u{1F4CC} {}", device.alias)
+            } else {
+                device.alias.clone()
+            };
+            let mut cells = vec![alias];
+            match list {
+                List::Paired => {
+                    cells.push(device.connected.to_string());
+                    cells.push(
+                        device
+                            .battery
+                            .map(|level| format!("{level}%"))
+                            .unwrap_or_default(),
+                    );
+                }
+                List::Unpaired => cells.push(rssi_bar(device.rssi)),
             }
             if let Some(index) = device.loading {
-                cells.push(THROBBERS[index % 4].into());
+                let throbbers = &self.theme.throbbers;
+                cells.push(
+                    throbbers
+                        .get(index % throbbers.len().max(1))
+                        .cloned()
+                        .unwrap_or_default(),
+                );
             }
             Row::new(cells)
         });
-        let widths = [Constraint::Ratio(1, 3); 3];
+        let widths = [Constraint::Ratio(1, 4); 4];
         let header = match list {
-            List::Unpaired => Row::new(["Alias"]),
-            List::Paired => Row::new(["Alias", "Connected"]),
+            List::Unpaired => Row::new(["Alias", "Signal"]),
+            List::Paired => Row::new(["Alias", "Connected", "Battery"]),
         };
         let table = Table::new(rows, widths)
             .header(header.style(Style::default().bold()).bottom_margin(1))
@@ -111,3 +214,25 @@ impl App {
         (table, TableState::default().with_selected(selected))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::rssi_bar;
+
+    #[test]
+    fn rssi_bar_none_is_blank() {
+        assert_eq!(rssi_bar(None), "");
+    }
+
+    #[test]
+    fn rssi_bar_bucket_boundaries() {
+        assert_eq!(rssi_bar(Some(-60)), "▂▄▆█ -60");
+        assert_eq!(rssi_bar(Some(-61)), "▂▄▆░ -61");
+        assert_eq!(rssi_bar(Some(-70)), "▂▄▆░ -70");
+        assert_eq!(rssi_bar(Some(-71)), "▂▄░░ -71");
+        assert_eq!(rssi_bar(Some(-80)), "▂▄░░ -80");
+        assert_eq!(rssi_bar(Some(-81)), "▂░░░ -81");
+        assert_eq!(rssi_bar(Some(-90)), "▂░░░ -90");
+        assert_eq!(rssi_bar(Some(-91)), "░░░░ -91");
+    }
+}